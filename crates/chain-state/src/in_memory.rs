@@ -4,26 +4,42 @@ use crate::{
     CanonStateNotification, CanonStateNotificationSender, CanonStateNotifications,
     ChainInfoTracker, MemoryOverlayStateProvider,
 };
-use alloy_consensus::{transaction::TransactionMeta, BlockHeader};
-use alloy_eips::{BlockHashOrNumber, BlockNumHash};
-use alloy_primitives::{map::HashMap, BlockNumber, TxHash, B256};
-use parking_lot::RwLock;
+use alloy_consensus::{transaction::TransactionMeta, BlockHeader, ReceiptWithBloom, TxReceipt};
+use alloy_eips::{eip4895::Withdrawals, BlockHashOrNumber, BlockNumHash};
+use alloy_primitives::{
+    map::{HashMap, HashSet},
+    Address, BlockNumber, Log, StorageKey, StorageValue, TxHash, B256,
+};
+use parking_lot::{Mutex, RwLock};
 use reth_chainspec::ChainInfo;
+use reth_errors::{ProviderError, ProviderResult};
 use reth_ethereum_primitives::EthPrimitives;
 use reth_execution_types::{Chain, ExecutionOutcome};
-use reth_metrics::{metrics::Gauge, Metrics};
+use reth_metrics::{
+    metrics::{Counter, Gauge},
+    Metrics,
+};
 use reth_primitives_traits::{
-    BlockBody as _, IndexedTx, NodePrimitives, RecoveredBlock, SealedBlock, SealedHeader,
+    Account, BlockBody as _, IndexedTx, NodePrimitives, RecoveredBlock, SealedBlock, SealedHeader,
     SignedTransaction,
 };
 use reth_storage_api::StateProviderBox;
 use reth_trie::{updates::TrieUpdates, HashedPostState};
-use std::{collections::BTreeMap, sync::Arc, time::Instant};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ops::{Bound, RangeInclusive},
+    sync::Arc,
+    time::Instant,
+};
 use tokio::sync::{broadcast, watch};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
 
 /// Size of the broadcast channel used to notify canonical state events.
 const CANON_STATE_NOTIFICATION_CHANNEL_SIZE: usize = 256;
 
+/// Number of most recent canonical state events retained for late subscribers.
+const CANON_STATE_NOTIFICATION_REPLAY_SIZE: usize = 16;
+
 /// Metrics for the in-memory state.
 #[derive(Metrics)]
 #[metrics(scope = "blockchain_tree.in_mem_state")]
@@ -34,6 +50,8 @@ pub(crate) struct InMemoryStateMetrics {
     pub(crate) latest_block: Gauge,
     /// The number of blocks in the in-memory state.
     pub(crate) num_blocks: Gauge,
+    /// The number of blocks inserted on top of an in-memory block other than the head.
+    pub(crate) forks: Counter,
 }
 
 /// Container type for in memory state data of the canonical chain.
@@ -96,15 +114,46 @@ impl<N: NodePrimitives> InMemoryState<N> {
         self.metrics.num_blocks.set(numbers.len() as f64);
     }
 
+    /// Asserts that the number index is consistent with the in-memory blocks.
+    ///
+    /// Every number must point to a block that is in memory, and the parent of every indexed block
+    /// above the lowest in-memory number must be in memory as well.
+    ///
+    /// # Locking behavior
+    ///
+    /// This tries to acquire read locks. Drop any write locks before calling this.
+    #[cfg(any(test, debug_assertions))]
+    pub(crate) fn assert_invariants(&self) {
+        let numbers = self.numbers.read();
+        let blocks = self.blocks.read();
+        let Some((lowest, _)) = numbers.first_key_value() else { return };
+        for (number, hash) in numbers.iter() {
+            let state = blocks
+                .get(hash)
+                .unwrap_or_else(|| panic!("block {number} ({hash}) is not in memory"));
+            assert_eq!(state.number(), *number, "block {hash} is indexed at the wrong number");
+            if number > lowest {
+                assert!(
+                    blocks.contains_key(&state.block_ref().recovered_block().parent_hash()),
+                    "parent of block {number} ({hash}) is not in memory"
+                );
+            }
+        }
+    }
+
     /// Returns the state for a given block hash.
     pub(crate) fn state_by_hash(&self, hash: B256) -> Option<Arc<BlockState<N>>> {
         self.blocks.read().get(&hash).cloned()
     }
 
     /// Returns the state for a given block number.
+    ///
+    /// The numbers lock is held while looking up the block, so the returned state is consistent
+    /// with the number index.
     pub(crate) fn state_by_number(&self, number: u64) -> Option<Arc<BlockState<N>>> {
-        let hash = self.hash_by_number(number)?;
-        self.state_by_hash(hash)
+        let numbers = self.numbers.read();
+        let hash = numbers.get(&number)?;
+        self.blocks.read().get(hash).cloned()
     }
 
     /// Returns the hash for a specific block number
@@ -124,10 +173,15 @@ impl<N: NodePrimitives> InMemoryState<N> {
         self.pending.borrow().clone()
     }
 
-    #[cfg(test)]
-    fn block_count(&self) -> usize {
+    /// Returns the number of blocks held in memory.
+    pub(crate) fn num_blocks(&self) -> usize {
         self.blocks.read().len()
     }
+
+    /// Returns `true` if a pending block is set.
+    pub(crate) fn has_pending(&self) -> bool {
+        self.pending.borrow().is_some()
+    }
 }
 
 /// Inner type to provide in memory state. It includes a chain tracker to be
@@ -141,6 +195,29 @@ pub(crate) struct CanonicalInMemoryStateInner<N: NodePrimitives> {
     pub(crate) in_memory_state: InMemoryState<N>,
     /// A broadcast stream that emits events when the canonical chain is updated.
     pub(crate) canon_state_notification_sender: CanonStateNotificationSender<N>,
+    /// The most recent canonical state events, replayed to late subscribers, and their sequence
+    /// numbers.
+    pub(crate) canon_state_notification_replay: Mutex<CanonStateNotificationReplay<N>>,
+    /// A broadcast stream that emits canonical state events together with their sequence number.
+    pub(crate) canon_state_notification_seq_sender:
+        broadcast::Sender<(u64, CanonStateNotification<N>)>,
+    /// Tracks the highest block that was persisted to disk and removed from memory.
+    pub(crate) persisted_block_sender: watch::Sender<Option<BlockNumHash>>,
+}
+
+/// The most recent canonical state events and the sequence number of the last one.
+#[derive(Debug)]
+pub(crate) struct CanonStateNotificationReplay<N: NodePrimitives> {
+    /// The events retained for replay, oldest first.
+    events: VecDeque<CanonStateNotification<N>>,
+    /// The sequence number of the most recently emitted event, `0` if none was emitted yet.
+    seq: u64,
+}
+
+impl<N: NodePrimitives> Default for CanonStateNotificationReplay<N> {
+    fn default() -> Self {
+        Self { events: VecDeque::new(), seq: 0 }
+    }
 }
 
 impl<N: NodePrimitives> CanonicalInMemoryStateInner<N> {
@@ -188,12 +265,18 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
         let chain_info_tracker = ChainInfoTracker::new(header, finalized, safe);
         let (canon_state_notification_sender, _) =
             broadcast::channel(CANON_STATE_NOTIFICATION_CHANNEL_SIZE);
+        let (canon_state_notification_seq_sender, _) =
+            broadcast::channel(CANON_STATE_NOTIFICATION_CHANNEL_SIZE);
+        let (persisted_block_sender, _) = watch::channel(None);
 
         Self {
             inner: Arc::new(CanonicalInMemoryStateInner {
                 chain_info_tracker,
                 in_memory_state,
                 canon_state_notification_sender,
+                canon_state_notification_replay: Default::default(),
+                canon_state_notification_seq_sender,
+                persisted_block_sender,
             }),
         }
     }
@@ -203,6 +286,17 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
         Self::new(HashMap::default(), BTreeMap::new(), None, None, None)
     }
 
+    /// Create an empty state with room for at least `capacity` in memory blocks.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::new(
+            HashMap::with_capacity_and_hasher(capacity, Default::default()),
+            BTreeMap::new(),
+            None,
+            None,
+            None,
+        )
+    }
+
     /// Create a new in memory state with the given local head and finalized header
     /// if it exists.
     pub fn with_head(
@@ -214,10 +308,16 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
         let in_memory_state = InMemoryState::default();
         let (canon_state_notification_sender, _) =
             broadcast::channel(CANON_STATE_NOTIFICATION_CHANNEL_SIZE);
+        let (canon_state_notification_seq_sender, _) =
+            broadcast::channel(CANON_STATE_NOTIFICATION_CHANNEL_SIZE);
+        let (persisted_block_sender, _) = watch::channel(None);
         let inner = CanonicalInMemoryStateInner {
             chain_info_tracker,
             in_memory_state,
             canon_state_notification_sender,
+            canon_state_notification_replay: Default::default(),
+            canon_state_notification_seq_sender,
+            persisted_block_sender,
         };
 
         Self { inner: Arc::new(inner) }
@@ -234,6 +334,31 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
             .map(|block| block.block_ref().recovered_block().clone_sealed_header())
     }
 
+    /// Returns the [`RecoveredBlock`], including the recovered senders, corresponding to the given
+    /// hash.
+    pub fn recovered_block_by_hash(&self, hash: B256) -> Option<RecoveredBlock<N::Block>> {
+        self.state_by_hash(hash).map(|block| block.block_ref().recovered_block().clone())
+    }
+
+    /// Returns the header corresponding to the given number.
+    pub fn header_by_number(&self, number: u64) -> Option<SealedHeader<N::BlockHeader>> {
+        self.state_by_number(number)
+            .map(|block| block.block_ref().recovered_block().clone_sealed_header())
+    }
+
+    /// Returns the in memory headers within the given block number range, in ascending order.
+    ///
+    /// Numbers in the range that are not in memory are skipped.
+    pub fn headers_range(&self, range: RangeInclusive<u64>) -> Vec<SealedHeader<N::BlockHeader>> {
+        let numbers = self.inner.in_memory_state.numbers.read();
+        let blocks = self.inner.in_memory_state.blocks.read();
+        numbers
+            .range(range)
+            .filter_map(|(_, hash)| blocks.get(hash))
+            .map(|state| state.block_ref().recovered_block().clone_sealed_header())
+            .collect()
+    }
+
     /// Clears all entries in the in memory state.
     pub fn clear_state(&self) {
         self.inner.clear()
@@ -252,6 +377,15 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
         self.inner.in_memory_state.update_metrics();
     }
 
+    /// Removes the pending block, if any.
+    ///
+    /// Note: This does not affect the canonical head.
+    pub fn clear_pending_block(&self) {
+        self.inner.in_memory_state.pending.send_modify(|p| {
+            p.take();
+        });
+    }
+
     /// Append new blocks to the in memory state.
     ///
     /// This removes all reorged blocks and appends the new blocks to the tracked chain and connects
@@ -266,6 +400,9 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
             let mut numbers = self.inner.in_memory_state.numbers.write();
             let mut blocks = self.inner.in_memory_state.blocks.write();
 
+            // the head before the update, used to detect blocks that fork off an in-memory block
+            let mut head = numbers.last_key_value().map(|(_, hash)| *hash);
+
             // we first remove the blocks from the reorged chain
             for block in reorged {
                 let hash = block.recovered_block().hash();
@@ -276,10 +413,18 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
 
             // insert the new blocks
             for block in new_blocks {
-                let parent = blocks.get(&block.recovered_block().parent_hash()).cloned();
+                let parent_hash = block.recovered_block().parent_hash();
+                let parent = blocks.get(&parent_hash).cloned();
+                // re-inserting a known block, e.g. when unwinding to an in memory ancestor, is not
+                // a fork
+                let known = blocks.contains_key(&block.recovered_block().hash());
+                if parent.is_some() && !known && head != Some(parent_hash) {
+                    self.inner.in_memory_state.metrics.forks.increment(1);
+                }
                 let block_state = BlockState::with_parent(block, parent);
                 let hash = block_state.hash();
                 let number = block_state.number();
+                head = Some(hash);
 
                 // append new blocks
                 blocks.insert(hash, Arc::new(block_state));
@@ -292,10 +437,13 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
             });
         }
         self.inner.in_memory_state.update_metrics();
+        #[cfg(debug_assertions)]
+        self.inner.in_memory_state.assert_invariants();
     }
 
     /// Update the in memory state with the given chain update.
     pub fn update_chain(&self, new_chain: NewCanonicalChain<N>) {
+        debug_assert_eq!(new_chain.validate_continuity(), Ok(()), "non-contiguous chain update");
         match new_chain {
             NewCanonicalChain::Commit { new } => {
                 self.update_blocks(new, vec![]);
@@ -306,6 +454,39 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
         }
     }
 
+    /// Reorgs the in memory state to the given new chain.
+    ///
+    /// The old chain is derived from the current head back to the parent of the first new block,
+    /// or back to the first in memory block if the common ancestor is already persisted.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn reorg_to(&self, new: Vec<ExecutedBlockWithTrieUpdates<N>>) {
+        let Some(first) = new.first() else { return };
+        let ancestor = first.recovered_block().parent_hash();
+        let old = self
+            .head_state()
+            .map(|head| {
+                head.chain()
+                    .take_while(|state| state.hash() != ancestor)
+                    .map(|state| state.block_ref().block.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.update_chain(NewCanonicalChain::Reorg { new, old });
+    }
+
+    /// Returns the canonical in memory blocks above the given persisted height, sorted by number.
+    ///
+    /// These are the blocks that still need to be persisted.
+    pub fn unpersisted_blocks(&self, persisted_height: u64) -> Vec<BlockNumHash> {
+        self.inner
+            .in_memory_state
+            .numbers
+            .read()
+            .range((Bound::Excluded(persisted_height), Bound::Unbounded))
+            .map(|(number, hash)| BlockNumHash::new(*number, *hash))
+            .collect()
+    }
+
     /// Removes blocks from the in memory state that are persisted to the given height.
     ///
     /// This will update the links between blocks and remove all blocks that are [..
@@ -363,6 +544,44 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
             });
         }
         self.inner.in_memory_state.update_metrics();
+        #[cfg(debug_assertions)]
+        self.inner.in_memory_state.assert_invariants();
+        self.inner.persisted_block_sender.send_replace(Some(persisted_num_hash));
+    }
+
+    /// Removes the in memory blocks up to and including the given persisted block and notifies the
+    /// [`Self::subscribe_persisted_block`] subscribers.
+    ///
+    /// This must be the block that was actually written to disk: if it was reorged out of memory
+    /// in the meantime this does nothing, see [`Self::remove_persisted_blocks`].
+    pub fn notify_persisted(&self, persisted: BlockNumHash) {
+        self.remove_persisted_blocks(persisted);
+    }
+
+    /// Removes all in memory blocks that are not reachable from the current head via parent links.
+    ///
+    /// These are abandoned side forks that are no longer part of the canonical chain. Does nothing
+    /// if there is no in memory head.
+    ///
+    /// Returns the number of pruned blocks.
+    pub fn prune_forks(&self) -> usize {
+        let pruned = {
+            // acquire locks, starting with the numbers lock
+            let numbers = self.inner.in_memory_state.numbers.read();
+            let mut blocks = self.inner.in_memory_state.blocks.write();
+
+            let Some(head) = numbers.last_key_value().and_then(|(_, hash)| blocks.get(hash)) else {
+                return 0
+            };
+            let canonical = head.chain().map(|state| state.hash()).collect::<HashSet<_>>();
+
+            let len = blocks.len();
+            blocks.retain(|hash, _| canonical.contains(hash));
+            len - blocks.len()
+        };
+        #[cfg(debug_assertions)]
+        self.inner.in_memory_state.assert_invariants();
+        pruned
     }
 
     /// Returns in memory state corresponding the given hash.
@@ -375,6 +594,11 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
         self.inner.in_memory_state.state_by_number(number)
     }
 
+    /// Returns the state root of the in memory block with the given number.
+    pub fn state_root_by_number(&self, number: u64) -> Option<B256> {
+        self.state_by_number(number).map(|state| state.state_root())
+    }
+
     /// Returns the in memory head state.
     pub fn head_state(&self) -> Option<Arc<BlockState<N>>> {
         self.inner.in_memory_state.head_state()
@@ -385,6 +609,47 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
         self.inner.in_memory_state.pending_state()
     }
 
+    /// Returns the number of blocks held in memory.
+    pub fn num_blocks(&self) -> usize {
+        self.inner.in_memory_state.num_blocks()
+    }
+
+    /// Returns the highest canonical block number in memory, if any.
+    pub fn max_block_number(&self) -> Option<u64> {
+        self.inner.in_memory_state.numbers.read().last_key_value().map(|(number, _)| *number)
+    }
+
+    /// Returns the lowest canonical block number in memory, if any.
+    pub fn min_block_number(&self) -> Option<u64> {
+        self.inner.in_memory_state.numbers.read().first_key_value().map(|(number, _)| *number)
+    }
+
+    /// Returns `true` if a pending block is set.
+    pub fn has_pending(&self) -> bool {
+        self.inner.in_memory_state.has_pending()
+    }
+
+    /// Returns the most recent in memory state: the pending state if present, otherwise the head
+    /// state.
+    pub fn pending_or_head_state(&self) -> Option<Arc<BlockState<N>>> {
+        self.pending_state().map(Arc::new).or_else(|| self.head_state())
+    }
+
+    /// Returns the receipts of the in memory canonical head, if any.
+    pub fn canonical_tip_receipts(&self) -> Option<Vec<N::Receipt>> {
+        self.head_state().map(|state| state.executed_block_receipts())
+    }
+
+    /// Returns the number of transactions in the pending block, if any.
+    pub fn pending_transactions_count(&self) -> Option<usize> {
+        self.inner
+            .in_memory_state
+            .pending
+            .borrow()
+            .as_ref()
+            .map(|state| state.block_ref().recovered_block().body().transaction_count())
+    }
+
     /// Returns the in memory pending `BlockNumHash`.
     pub fn pending_block_num_hash(&self) -> Option<BlockNumHash> {
         self.inner
@@ -428,6 +693,15 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
         self.inner.chain_info_tracker.set_canonical_head(header);
     }
 
+    /// Sets the in-memory block with the given hash as the canonical head.
+    ///
+    /// Returns an error if the block is not part of the in-memory state.
+    pub fn set_canonical_head_by_hash(&self, hash: B256) -> ProviderResult<()> {
+        let header = self.header_by_hash(hash).ok_or(ProviderError::BlockHashNotFound(hash))?;
+        self.set_canonical_head(header);
+        Ok(())
+    }
+
     /// Safe head setter.
     pub fn set_safe(&self, header: SealedHeader<N::BlockHeader>) {
         self.inner.chain_info_tracker.set_safe(header);
@@ -438,6 +712,24 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
         self.inner.chain_info_tracker.set_finalized(header);
     }
 
+    /// Marks the in-memory block with the given hash as finalized.
+    ///
+    /// Returns an error if the block is not part of the in-memory state.
+    pub fn finalize_block(&self, hash: B256) -> ProviderResult<()> {
+        let header = self.header_by_hash(hash).ok_or(ProviderError::BlockHashNotFound(hash))?;
+        self.set_finalized(header);
+        Ok(())
+    }
+
+    /// Marks the in-memory block with the given hash as safe.
+    ///
+    /// Returns an error if the block is not part of the in-memory state.
+    pub fn mark_safe(&self, hash: B256) -> ProviderResult<()> {
+        let header = self.header_by_hash(hash).ok_or(ProviderError::BlockHashNotFound(hash))?;
+        self.set_safe(header);
+        Ok(())
+    }
+
     /// Canonical head getter.
     pub fn get_canonical_head(&self) -> SealedHeader<N::BlockHeader> {
         self.inner.chain_info_tracker.get_canonical_head()
@@ -463,6 +755,34 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
         self.pending_sealed_header().map(|sealed_header| sealed_header.unseal())
     }
 
+    /// Returns the base fee of the pending block, if any.
+    pub fn pending_base_fee(&self) -> Option<u64> {
+        self.pending_state()
+            .and_then(|state| state.block_ref().recovered_block().header().base_fee_per_gas())
+    }
+
+    /// Returns the transactions root of the pending block, if any.
+    pub fn pending_transactions_root(&self) -> Option<B256> {
+        self.pending_state()
+            .map(|state| state.block_ref().recovered_block().header().transactions_root())
+    }
+
+    /// Returns the ommers hash of the pending block, if any.
+    pub fn pending_ommers_hash(&self) -> Option<B256> {
+        self.pending_state().map(|state| state.block_ref().recovered_block().header().ommers_hash())
+    }
+
+    /// Returns the withdrawals root of the pending block, if any.
+    pub fn pending_withdrawals_root(&self) -> Option<B256> {
+        self.pending_state()
+            .and_then(|state| state.block_ref().recovered_block().header().withdrawals_root())
+    }
+
+    /// Returns the base fee of the canonical head, if any.
+    pub fn canonical_tip_base_fee(&self) -> Option<u64> {
+        self.inner.chain_info_tracker.get_canonical_head().base_fee_per_gas()
+    }
+
     /// Returns the `SealedBlock` corresponding to the pending state.
     pub fn pending_block(&self) -> Option<SealedBlock<N::Block>> {
         self.pending_state()
@@ -493,6 +813,57 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
         self.inner.canon_state_notification_sender.subscribe()
     }
 
+    /// Subscribe to new blocks events, together with their sequence number.
+    ///
+    /// Sequence numbers are assigned consecutively, starting at `1`, in the order the events are
+    /// sent. A subscriber that observes a gap between two consecutive sequence numbers missed the
+    /// events in between.
+    ///
+    /// This is a separate channel so that [`CanonStateNotificationSender`] and the many
+    /// [`CanonStateNotifications`] consumers keep their item type. Events are only cloned into
+    /// this channel while it has subscribers.
+    pub fn subscribe_canon_state_with_seq(
+        &self,
+    ) -> broadcast::Receiver<(u64, CanonStateNotification<N>)> {
+        self.inner.canon_state_notification_seq_sender.subscribe()
+    }
+
+    /// Subscribe to new blocks events, first replaying up to `n` of the most recent events.
+    ///
+    /// At most `CANON_STATE_NOTIFICATION_REPLAY_SIZE` events are retained for replay. Lagged
+    /// live events are skipped.
+    pub fn subscribe_with_replay(
+        &self,
+        n: usize,
+    ) -> impl Stream<Item = CanonStateNotification<N>> + use<N> {
+        let replay = self.inner.canon_state_notification_replay.lock();
+        let events = &replay.events;
+        let buffered =
+            events.iter().skip(events.len().saturating_sub(n)).cloned().collect::<Vec<_>>();
+        let live = BroadcastStream::new(self.subscribe_canon_state()).filter_map(Result::ok);
+        tokio_stream::iter(buffered).chain(live)
+    }
+
+    /// Returns a stream of canonical head updates.
+    ///
+    /// Emits the tip header of every commit or reorg notification. Reverts without new blocks and
+    /// lagged notifications are skipped.
+    pub fn subscribe_head_changes(
+        &self,
+    ) -> impl Stream<Item = SealedHeader<N::BlockHeader>> + use<N> {
+        BroadcastStream::new(self.subscribe_canon_state()).filter_map(|notification| {
+            notification.ok()?.tip_checked().map(|tip| tip.clone_sealed_header())
+        })
+    }
+
+    /// Subscribe to persisted block events.
+    ///
+    /// Emits the highest persisted block every time blocks are removed from memory because they
+    /// were written to disk.
+    pub fn subscribe_persisted_block(&self) -> watch::Receiver<Option<BlockNumHash>> {
+        self.inner.persisted_block_sender.subscribe()
+    }
+
     /// Subscribe to new safe block events.
     pub fn subscribe_safe_block(&self) -> watch::Receiver<Option<SealedHeader<N::BlockHeader>>> {
         self.inner.chain_info_tracker.subscribe_safe_block()
@@ -507,6 +878,18 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
 
     /// Attempts to send a new [`CanonStateNotification`] to all active Receiver handles.
     pub fn notify_canon_state(&self, event: CanonStateNotification<N>) {
+        // hold the replay lock while sending, so replaying subscribers neither miss nor duplicate
+        // this event
+        let mut replay = self.inner.canon_state_notification_replay.lock();
+        if replay.events.len() == CANON_STATE_NOTIFICATION_REPLAY_SIZE {
+            replay.events.pop_front();
+        }
+        replay.events.push_back(event.clone());
+        replay.seq += 1;
+        let seq_sender = &self.inner.canon_state_notification_seq_sender;
+        if seq_sender.receiver_count() > 0 {
+            seq_sender.send((replay.seq, event.clone())).ok();
+        }
         self.inner.canon_state_notification_sender.send(event).ok();
     }
 
@@ -528,6 +911,20 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
         MemoryOverlayStateProvider::new(historical, in_memory)
     }
 
+    /// Return state provider with reference to the in-memory blocks of the canonical chain that
+    /// overlay database state, see [`Self::state_provider`].
+    ///
+    /// If there is no in-memory head, the returned provider only reads from the historical state.
+    pub fn latest_state_provider(
+        &self,
+        historical: StateProviderBox,
+    ) -> MemoryOverlayStateProvider<N> {
+        match self.head_state() {
+            Some(head) => self.state_provider(head.hash(), historical),
+            None => MemoryOverlayStateProvider::new(historical, Vec::new()),
+        }
+    }
+
     /// Returns an iterator over all __canonical blocks__ in the in-memory state, from newest to
     /// oldest (highest to lowest).
     ///
@@ -536,6 +933,15 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
         self.inner.in_memory_state.head_state().into_iter().flat_map(|head| head.iter())
     }
 
+    /// Returns an iterator over the in-memory block with the given hash and all of its in-memory
+    /// ancestors, from newest to oldest (highest to lowest).
+    ///
+    /// The iterator stops before the on-disk [`BlockState::anchor`] and is empty if the hash is
+    /// not in memory.
+    pub fn ancestors_iter(&self, hash: B256) -> impl Iterator<Item = Arc<BlockState<N>>> {
+        self.state_by_hash(hash).into_iter().flat_map(|state| state.iter())
+    }
+
     /// Returns [`SignedTransaction`] type for the given `TxHash` if found.
     pub fn transaction_by_hash(&self, hash: TxHash) -> Option<N::SignedTx> {
         for block_state in self.canonical_chain() {
@@ -643,6 +1049,12 @@ impl<N: NodePrimitives> BlockState<N> {
         receipts.first().cloned().unwrap_or_default()
     }
 
+    /// Returns the receipt of the transaction at the given index in the block, or `None` if the
+    /// index is out of range.
+    pub fn receipt_by_index(&self, index: usize) -> Option<N::Receipt> {
+        self.receipts().first()?.get(index).cloned()
+    }
+
     /// Returns a vector of __parent__ `BlockStates`.
     ///
     /// The block state order in the output vector is newest to oldest (highest to lowest):
@@ -668,6 +1080,11 @@ impl<N: NodePrimitives> BlockState<N> {
         std::iter::successors(Some(self), |state| state.parent.as_deref())
     }
 
+    /// Returns the number of in memory parents of this block, back to the [`Self::anchor`].
+    pub fn depth(&self) -> usize {
+        self.chain().skip(1).count()
+    }
+
     /// Appends the parent chain of this [`BlockState`] to the given vector.
     pub fn append_parent_chain<'a>(&'a self, chain: &mut Vec<&'a Self>) {
         chain.extend(self.parent_state_chain());
@@ -698,6 +1115,26 @@ impl<N: NodePrimitives> BlockState<N> {
         })
     }
 
+    /// Tries to find the most recent in memory state of an account in the chain ending at this
+    /// block.
+    ///
+    /// Returns `None` if the account was not touched in memory, and `Some(None)` if it was
+    /// destroyed.
+    pub fn account_on_chain(&self, address: &Address) -> Option<Option<Account>> {
+        self.chain()
+            .find_map(|block_state| block_state.block_ref().execution_outcome().account(address))
+    }
+
+    /// Tries to find the most recent in memory value of a storage slot in the chain ending at this
+    /// block.
+    ///
+    /// Returns `None` if the slot was not touched in memory.
+    pub fn storage_on_chain(&self, address: &Address, key: StorageKey) -> Option<StorageValue> {
+        self.chain().find_map(|block_state| {
+            block_state.block_ref().execution_outcome().storage(address, key.into())
+        })
+    }
+
     /// Tries to find a transaction by [`TxHash`] in the chain ending at this block.
     pub fn transaction_on_chain(&self, hash: TxHash) -> Option<N::SignedTx> {
         self.chain().find_map(|block_state| {
@@ -755,12 +1192,42 @@ impl<N: NodePrimitives> ExecutedBlock<N> {
         &self.recovered_block
     }
 
+    /// Returns an owned copy of the [`RecoveredBlock`], including the recovered senders.
+    #[inline]
+    pub fn to_recovered_block(&self) -> RecoveredBlock<N::Block> {
+        RecoveredBlock::clone(&self.recovered_block)
+    }
+
     /// Returns a reference to the block's execution outcome
     #[inline]
     pub fn execution_outcome(&self) -> &ExecutionOutcome<N::Receipt> {
         &self.execution_output
     }
 
+    /// Returns the receipts of the block together with their computed logs bloom.
+    pub fn receipts_with_bloom(&self) -> Vec<ReceiptWithBloom<N::Receipt>> {
+        self.execution_output
+            .receipts
+            .first()
+            .into_iter()
+            .flatten()
+            .cloned()
+            .map(TxReceipt::into_with_bloom)
+            .collect()
+    }
+
+    /// Returns an iterator over all logs of the block in order, together with the index of the
+    /// receipt, and thus the transaction, that emitted them.
+    pub fn logs(&self) -> impl Iterator<Item = (usize, &Log)> + '_ {
+        self.execution_output
+            .receipts
+            .first()
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .flat_map(|(idx, receipt)| receipt.logs().iter().map(move |log| (idx, log)))
+    }
+
     /// Returns a reference to the hashed state result of the execution outcome
     #[inline]
     pub fn hashed_state(&self) -> &HashedPostState {
@@ -772,6 +1239,78 @@ impl<N: NodePrimitives> ExecutedBlock<N> {
     pub fn block_number(&self) -> BlockNumber {
         self.recovered_block.header().number()
     }
+
+    /// Returns `true` if the block has no transactions.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.recovered_block.body().transactions().is_empty()
+    }
+
+    /// Returns the gas used by the block.
+    #[inline]
+    pub fn gas_used(&self) -> u64 {
+        self.recovered_block.header().gas_used()
+    }
+
+    /// Returns the withdrawals of the block, if any.
+    #[inline]
+    pub fn withdrawals(&self) -> Option<&Withdrawals> {
+        self.recovered_block.body().withdrawals()
+    }
+
+    /// Returns the blob gas used by the block, if any.
+    #[inline]
+    pub fn blob_gas_used(&self) -> Option<u64> {
+        self.recovered_block.header().blob_gas_used()
+    }
+
+    /// Returns the excess blob gas of the block, if any.
+    #[inline]
+    pub fn excess_blob_gas(&self) -> Option<u64> {
+        self.recovered_block.header().excess_blob_gas()
+    }
+
+    /// Returns an iterator over the versioned hashes of all blobs in the block.
+    #[inline]
+    pub fn blob_versioned_hashes_iter(&self) -> impl Iterator<Item = &B256> + '_ {
+        self.recovered_block.body().blob_versioned_hashes_iter()
+    }
+
+    /// Returns a [`HeaderSummary`] of the commonly needed header fields of the block.
+    pub fn header_summary(&self) -> HeaderSummary {
+        let header = self.recovered_block.header();
+        HeaderSummary {
+            number: header.number(),
+            hash: self.recovered_block.hash(),
+            parent_hash: header.parent_hash(),
+            state_root: header.state_root(),
+            gas_used: header.gas_used(),
+            gas_limit: header.gas_limit(),
+            timestamp: header.timestamp(),
+            base_fee_per_gas: header.base_fee_per_gas(),
+        }
+    }
+}
+
+/// The commonly needed header fields of an [`ExecutedBlock`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderSummary {
+    /// Block number.
+    pub number: BlockNumber,
+    /// Block hash.
+    pub hash: B256,
+    /// Hash of the parent block.
+    pub parent_hash: B256,
+    /// State root after executing the block.
+    pub state_root: B256,
+    /// Gas used by the block.
+    pub gas_used: u64,
+    /// Gas limit of the block.
+    pub gas_limit: u64,
+    /// Timestamp of the block.
+    pub timestamp: u64,
+    /// Base fee of the block, if any.
+    pub base_fee_per_gas: Option<u64>,
 }
 
 /// Trie updates that result from calculating the state root for the block.
@@ -899,7 +1438,56 @@ pub enum NewCanonicalChain<N: NodePrimitives = EthPrimitives> {
     },
 }
 
+/// Error returned by [`NewCanonicalChain::validate_continuity`] if the new blocks do not form a
+/// contiguous chain.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ChainContinuityError {
+    /// The parent hash of a block does not match the hash of the previous block.
+    #[error("block {number} has parent hash {parent_hash}, expected {expected}")]
+    ParentHashMismatch {
+        /// Number of the block.
+        number: BlockNumber,
+        /// Parent hash of the block.
+        parent_hash: B256,
+        /// Hash of the previous block.
+        expected: B256,
+    },
+    /// The number of a block does not directly follow the number of the previous block.
+    #[error("block {number} does not follow previous block {previous}")]
+    NumberGap {
+        /// Number of the block.
+        number: BlockNumber,
+        /// Number of the previous block.
+        previous: BlockNumber,
+    },
+}
+
 impl<N: NodePrimitives<SignedTx: SignedTransaction>> NewCanonicalChain<N> {
+    /// Checks that the new blocks form a contiguous chain, where each block is the child of the
+    /// previous one.
+    pub fn validate_continuity(&self) -> Result<(), ChainContinuityError> {
+        let new = match self {
+            Self::Commit { new } | Self::Reorg { new, .. } => new,
+        };
+        for pair in new.windows(2) {
+            let (previous, block) = (pair[0].recovered_block(), pair[1].recovered_block());
+            if block.number() != previous.number() + 1 {
+                return Err(ChainContinuityError::NumberGap {
+                    number: block.number(),
+                    previous: previous.number(),
+                })
+            }
+            if block.parent_hash() != previous.hash() {
+                return Err(ChainContinuityError::ParentHashMismatch {
+                    number: block.number(),
+                    parent_hash: block.parent_hash(),
+                    expected: previous.hash(),
+                })
+            }
+        }
+        Ok(())
+    }
+
     /// Returns the length of the new chain.
     pub const fn new_block_count(&self) -> usize {
         match self {
@@ -915,6 +1503,23 @@ impl<N: NodePrimitives<SignedTx: SignedTransaction>> NewCanonicalChain<N> {
         }
     }
 
+    /// Returns an iterator over the blocks of the new chain.
+    pub fn blocks_iter(&self) -> impl Iterator<Item = &ExecutedBlock<N>> + '_ {
+        match self {
+            Self::Commit { new } | Self::Reorg { new, .. } => new.iter().map(|block| &block.block),
+        }
+    }
+
+    /// Returns an iterator over the blocks of the reorged chain.
+    ///
+    /// This is empty for [`Self::Commit`].
+    pub fn reorged_iter(&self) -> impl Iterator<Item = &ExecutedBlock<N>> + '_ {
+        match self {
+            Self::Commit { .. } => [].iter(),
+            Self::Reorg { old, .. } => old.iter(),
+        }
+    }
+
     /// Converts the new chain into a notification that will be emitted to listeners
     pub fn to_chain_notification(&self) -> CanonStateNotification<N> {
         match self {
@@ -948,6 +1553,34 @@ impl<N: NodePrimitives<SignedTx: SignedTransaction>> NewCanonicalChain<N> {
         }
     }
 
+    /// Converts the new chain into a notification that will be emitted to listeners.
+    ///
+    /// Unlike [`Self::to_chain_notification`], this moves the blocks and execution outcomes into
+    /// the notification and only clones them if they are still shared.
+    pub fn into_notification(self) -> CanonStateNotification<N> {
+        fn into_chain<N: NodePrimitives>(
+            blocks: impl IntoIterator<Item = ExecutedBlock<N>>,
+        ) -> Arc<Chain<N>> {
+            Arc::new(blocks.into_iter().fold(Chain::default(), |mut chain, exec| {
+                chain.append_block(
+                    Arc::unwrap_or_clone(exec.recovered_block),
+                    Arc::unwrap_or_clone(exec.execution_output),
+                );
+                chain
+            }))
+        }
+
+        match self {
+            Self::Commit { new } => {
+                CanonStateNotification::Commit { new: into_chain(new.into_iter().map(|b| b.block)) }
+            }
+            Self::Reorg { new, old } => CanonStateNotification::Reorg {
+                new: into_chain(new.into_iter().map(|b| b.block)),
+                old: into_chain(old),
+            },
+        }
+    }
+
     /// Returns the new tip of the chain.
     ///
     /// Returns the new tip for [`Self::Reorg`] and [`Self::Commit`] variants which commit at least
@@ -965,12 +1598,16 @@ impl<N: NodePrimitives<SignedTx: SignedTransaction>> NewCanonicalChain<N> {
 mod tests {
     use super::*;
     use crate::test_utils::TestBlockBuilder;
-    use alloy_eips::eip7685::Requests;
-    use alloy_primitives::{Address, BlockNumber, Bytes, StorageKey, StorageValue};
+    use alloy_consensus::{
+        proofs::{calculate_transaction_root, calculate_withdrawals_root},
+        Header, EMPTY_OMMER_ROOT_HASH,
+    };
+    use alloy_eips::{eip1559::INITIAL_BASE_FEE, eip7685::Requests};
+    use alloy_primitives::{BlockNumber, Bloom, BloomInput, Bytes, Log, U256};
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
     use rand::Rng;
-    use reth_errors::ProviderResult;
     use reth_ethereum_primitives::{EthPrimitives, Receipt};
-    use reth_primitives_traits::{Account, Bytecode};
+    use reth_primitives_traits::Bytecode;
     use reth_storage_api::{
         AccountReader, BlockHashReader, BytecodeReader, HashedPostStateProvider,
         StateProofProvider, StateProvider, StateRootProvider, StorageRootProvider,
@@ -979,6 +1616,7 @@ mod tests {
         AccountProof, HashedStorage, MultiProof, MultiProofTargets, StorageMultiProof,
         StorageProof, TrieInput,
     };
+    use std::task::{Context, Poll, Waker};
 
     fn create_mock_state(
         test_block_builder: &mut TestBlockBuilder<EthPrimitives>,
@@ -1134,6 +1772,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_in_memory_state_invariants() {
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..4).collect();
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
+        state.remove_persisted_blocks(blocks[0].recovered_block().num_hash());
+        state.inner.in_memory_state.assert_invariants();
+    }
+
+    #[test]
+    #[should_panic(expected = "is not in memory")]
+    fn test_in_memory_state_invariants_missing_block() {
+        let numbers = BTreeMap::from([(1, B256::random())]);
+        let in_memory_state: InMemoryState = InMemoryState::new(HashMap::default(), numbers, None);
+        in_memory_state.assert_invariants();
+    }
+
+    #[test]
+    #[should_panic(expected = "parent of block")]
+    fn test_in_memory_state_invariants_missing_parent() {
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let block1 = test_block_builder.get_executed_block_with_number(1, B256::random());
+        // block 2 is not a child of block 1
+        let block2 = test_block_builder.get_executed_block_with_number(2, B256::random());
+        let mut state_by_hash = HashMap::default();
+        let mut hash_by_number = BTreeMap::new();
+        for block in [block1, block2] {
+            let num_hash = block.recovered_block().num_hash();
+            state_by_hash.insert(num_hash.hash, Arc::new(BlockState::new(block)));
+            hash_by_number.insert(num_hash.number, num_hash.hash);
+        }
+        let in_memory_state: InMemoryState =
+            InMemoryState::new(state_by_hash, hash_by_number, None);
+        in_memory_state.assert_invariants();
+    }
+
     #[test]
     fn test_in_memory_state_impl_state_by_hash() {
         let mut state_by_hash = HashMap::default();
@@ -1228,6 +1903,49 @@ mod tests {
         assert_eq!(state.state_root(), block.recovered_block().state_root);
     }
 
+    #[test]
+    fn test_state_account_and_storage_on_chain() {
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let address = Address::random();
+        let slot = B256::random();
+
+        let execution_outcome = |number: u64, nonce: u64, value: u64| {
+            let bundle = revm_database::BundleState::builder(number..=number)
+                .state_present_account_info(
+                    address,
+                    revm_state::AccountInfo { nonce, ..Default::default() },
+                )
+                .state_storage(
+                    address,
+                    std::iter::once((slot.into(), (U256::ZERO, U256::from(value)))).collect(),
+                )
+                .build();
+            Arc::new(ExecutionOutcome::new(bundle, vec![vec![]], number, vec![]))
+        };
+
+        let mut block1 = test_block_builder.get_executed_block_with_number(1, B256::random());
+        block1.block.execution_output = execution_outcome(1, 1, 1);
+        let mut block2 =
+            test_block_builder.get_executed_block_with_number(2, block1.recovered_block().hash());
+        block2.block.execution_output = execution_outcome(2, 2, 2);
+        let block3 =
+            test_block_builder.get_executed_block_with_number(3, block2.recovered_block().hash());
+
+        let state1 = Arc::new(BlockState::new(block1));
+        let state2 = Arc::new(BlockState::with_parent(block2, Some(state1.clone())));
+        let state3 = BlockState::with_parent(block3, Some(state2));
+
+        // the descendant overwrites the value written in the ancestor
+        assert_eq!(state3.account_on_chain(&address).unwrap().unwrap().nonce, 2);
+        assert_eq!(state3.storage_on_chain(&address, slot), Some(U256::from(2)));
+        assert_eq!(state1.account_on_chain(&address).unwrap().unwrap().nonce, 1);
+        assert_eq!(state1.storage_on_chain(&address, slot), Some(U256::from(1)));
+
+        // untouched account and slot
+        assert_eq!(state3.account_on_chain(&Address::random()), None);
+        assert_eq!(state3.storage_on_chain(&address, B256::random()), None);
+    }
+
     #[test]
     fn test_state_receipts() {
         let receipts = vec![vec![Receipt::default()]];
@@ -1240,6 +1958,23 @@ mod tests {
         assert_eq!(state.receipts(), &receipts);
     }
 
+    #[test]
+    fn test_state_receipt_by_index() {
+        let receipts = vec![vec![
+            Receipt { cumulative_gas_used: 21_000, ..Default::default() },
+            Receipt { cumulative_gas_used: 42_000, ..Default::default() },
+        ]];
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let block =
+            test_block_builder.get_executed_block_with_receipts(receipts.clone(), B256::random());
+
+        let state = BlockState::new(block);
+
+        assert_eq!(state.receipt_by_index(0), Some(receipts[0][0].clone()));
+        assert_eq!(state.receipt_by_index(1), Some(receipts[0][1].clone()));
+        assert_eq!(state.receipt_by_index(2), None);
+    }
+
     #[test]
     fn test_in_memory_state_chain_update() {
         let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
@@ -1268,24 +2003,69 @@ mod tests {
             block2.recovered_block().hash()
         );
 
-        assert_eq!(state.inner.in_memory_state.block_count(), 1);
+        assert_eq!(state.inner.in_memory_state.num_blocks(), 1);
     }
 
     #[test]
-    fn test_in_memory_state_set_pending_block() {
+    fn test_in_memory_state_headers_range() {
         let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
         let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..4).collect();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
 
-        // First random block
-        let block1 = test_block_builder.get_executed_block_with_number(0, B256::random());
+        let headers = state.headers_range(2..=10);
+        assert_eq!(
+            headers,
+            vec![
+                blocks[1].recovered_block().clone_sealed_header(),
+                blocks[2].recovered_block().clone_sealed_header()
+            ]
+        );
+        assert!(state.headers_range(4..=10).is_empty());
+    }
 
-        // Second block with parent hash of the first block
-        let block2 =
-            test_block_builder.get_executed_block_with_number(1, block1.recovered_block().hash());
+    #[test]
+    fn test_in_memory_state_state_root_by_number() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..3).collect();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
 
-        // Commit the two blocks
-        let chain = NewCanonicalChain::Commit { new: vec![block1.clone(), block2.clone()] };
-        state.update_chain(chain);
+        assert_eq!(state.state_root_by_number(1), Some(blocks[0].recovered_block().state_root));
+        assert_eq!(state.state_root_by_number(2), Some(blocks[1].recovered_block().state_root));
+        assert_eq!(state.state_root_by_number(3), None);
+    }
+
+    #[test]
+    fn test_in_memory_state_header_by_number() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let blocks = test_block_builder.get_executed_blocks(1..4).collect::<Vec<_>>();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
+
+        for block in &blocks {
+            let header = state.header_by_number(block.block_number()).unwrap();
+            assert_eq!(header, block.recovered_block().clone_sealed_header());
+            assert_eq!(Some(header), state.header_by_hash(block.recovered_block().hash()));
+        }
+        assert_eq!(state.header_by_number(4), None);
+    }
+
+    #[test]
+    fn test_in_memory_state_set_pending_block() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+
+        // First random block
+        let block1 = test_block_builder.get_executed_block_with_number(0, B256::random());
+
+        // Second block with parent hash of the first block
+        let block2 =
+            test_block_builder.get_executed_block_with_number(1, block1.recovered_block().hash());
+
+        // Commit the two blocks
+        let chain = NewCanonicalChain::Commit { new: vec![block1.clone(), block2.clone()] };
+        state.update_chain(chain);
 
         // Assert that the pending state is None before setting it
         assert!(state.pending_state().is_none());
@@ -1327,6 +2107,509 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_in_memory_state_clear_pending_block() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+
+        let block1 = test_block_builder.get_executed_block_with_number(0, B256::random());
+        state.update_chain(NewCanonicalChain::Commit { new: vec![block1.clone()] });
+
+        let receipts = vec![vec![Receipt::default()]];
+        let pending = test_block_builder
+            .get_executed_block_with_receipts(receipts.clone(), block1.recovered_block().hash());
+        state.set_pending_block(pending.clone());
+
+        assert_eq!(
+            state.pending_block_and_receipts().unwrap(),
+            (pending.recovered_block().clone(), receipts[0].clone())
+        );
+        assert_eq!(state.head_state().unwrap().hash(), block1.recovered_block().hash());
+
+        state.clear_pending_block();
+
+        assert!(state.pending_state().is_none());
+        assert_eq!(state.head_state().unwrap().hash(), block1.recovered_block().hash());
+    }
+
+    #[test]
+    fn test_in_memory_state_finalize_and_mark_safe() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let blocks = test_block_builder.get_executed_blocks(1..3).collect::<Vec<_>>();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
+
+        let safe = blocks[1].recovered_block();
+        let finalized = blocks[0].recovered_block();
+        state.mark_safe(safe.hash()).unwrap();
+        state.finalize_block(finalized.hash()).unwrap();
+
+        assert_eq!(state.get_safe_header(), Some(safe.clone_sealed_header()));
+        assert_eq!(state.get_finalized_header(), Some(finalized.clone_sealed_header()));
+
+        let unknown = B256::random();
+        assert!(matches!(
+            state.mark_safe(unknown),
+            Err(ProviderError::BlockHashNotFound(hash)) if hash == unknown
+        ));
+        assert!(matches!(
+            state.finalize_block(unknown),
+            Err(ProviderError::BlockHashNotFound(hash)) if hash == unknown
+        ));
+
+        // unknown hashes leave the previous values untouched
+        assert_eq!(state.get_safe_num_hash(), Some(safe.num_hash()));
+        assert_eq!(state.get_finalized_num_hash(), Some(finalized.num_hash()));
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_hash_by_number() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..4).collect();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
+
+        for block in &blocks {
+            let number = block.recovered_block().number();
+            assert_eq!(state.hash_by_number(number), Some(block.recovered_block().hash()));
+            assert_eq!(
+                state.hash_by_number(number),
+                state.header_by_number(number).map(|header| header.hash())
+            );
+        }
+        assert_eq!(state.hash_by_number(4), None);
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_recovered_block_by_hash() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let block = test_block_builder.get_executed_block_with_number(1, B256::random());
+        let hash = block.recovered_block().hash();
+        assert!(state.recovered_block_by_hash(hash).is_none());
+
+        state.update_chain(NewCanonicalChain::Commit { new: vec![block.clone()] });
+
+        let recovered = state.recovered_block_by_hash(hash).unwrap();
+        assert_eq!(&recovered, block.recovered_block());
+        assert_eq!(recovered.senders(), block.recovered_block().senders());
+        assert!(state.recovered_block_by_hash(B256::random()).is_none());
+    }
+
+    #[test]
+    fn test_in_memory_state_set_canonical_head_by_hash() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let blocks = test_block_builder.get_executed_blocks(1..3).collect::<Vec<_>>();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
+
+        let head = blocks[1].recovered_block();
+        state.set_canonical_head_by_hash(head.hash()).unwrap();
+        assert_eq!(state.get_canonical_head(), head.clone_sealed_header());
+
+        let unknown = B256::random();
+        assert!(matches!(
+            state.set_canonical_head_by_hash(unknown),
+            Err(ProviderError::BlockHashNotFound(hash)) if hash == unknown
+        ));
+        assert_eq!(state.get_canonical_head(), head.clone_sealed_header());
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_subscribe_persisted_block() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let mut persisted = state.subscribe_persisted_block();
+        assert_eq!(*persisted.borrow(), None);
+
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..4).collect();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
+        assert!(!persisted.has_changed().unwrap());
+
+        let persisted_num_hash = blocks[1].recovered_block().num_hash();
+        state.remove_persisted_blocks(persisted_num_hash);
+        assert!(persisted.has_changed().unwrap());
+        assert_eq!(*persisted.borrow_and_update(), Some(persisted_num_hash));
+        assert_eq!(state.num_blocks(), 1);
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_notify_persisted() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let mut persisted = state.subscribe_persisted_block();
+
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..4).collect();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
+
+        // unknown blocks are ignored
+        state.notify_persisted(BlockNumHash::new(2, B256::random()));
+        assert!(!persisted.has_changed().unwrap());
+        assert_eq!(state.num_blocks(), 3);
+
+        state.notify_persisted(blocks[1].recovered_block().num_hash());
+        assert!(persisted.has_changed().unwrap());
+        assert_eq!(*persisted.borrow_and_update(), Some(blocks[1].recovered_block().num_hash()));
+        assert_eq!(state.num_blocks(), 1);
+        assert_eq!(state.min_block_number(), Some(3));
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_notify_persisted_after_reorg() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let persisted = state.subscribe_persisted_block();
+
+        // A1..A3 are in memory while the persistence task writes A1 and A2
+        let chain_a: Vec<_> = test_block_builder.get_executed_blocks(1..4).collect();
+        state.update_chain(NewCanonicalChain::Commit { new: chain_a.clone() });
+
+        // meanwhile B2 and B3 reorg onto A1
+        let b2 = test_block_builder
+            .get_executed_block_with_number(2, chain_a[0].recovered_block().hash());
+        let b3 = test_block_builder.get_executed_block_with_number(3, b2.recovered_block().hash());
+        state.reorg_to(vec![b2.clone(), b3.clone()]);
+
+        // A2 is no longer in memory, so the new canonical blocks at the same height are kept
+        state.notify_persisted(chain_a[1].recovered_block().num_hash());
+        assert!(!persisted.has_changed().unwrap());
+        assert_eq!(state.num_blocks(), 3);
+        assert!(state.state_by_hash(chain_a[0].recovered_block().hash()).is_some());
+        assert!(state.state_by_hash(b2.recovered_block().hash()).is_some());
+        assert!(state.state_by_hash(b3.recovered_block().hash()).is_some());
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_ancestors_iter() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        assert_eq!(state.ancestors_iter(B256::random()).count(), 0);
+
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..5).collect();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
+
+        let ancestors: Vec<_> = state.ancestors_iter(blocks[2].recovered_block().hash()).collect();
+        assert_eq!(ancestors.iter().map(|state| state.number()).collect::<Vec<_>>(), vec![3, 2, 1]);
+
+        // the iteration stops right above the on-disk anchor
+        let anchor = ancestors[0].anchor();
+        assert_eq!(anchor, blocks[0].recovered_block().parent_num_hash());
+        assert_eq!(
+            ancestors.last().unwrap().block_ref().recovered_block().parent_hash(),
+            anchor.hash
+        );
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_min_max_block_number() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        assert_eq!(state.min_block_number(), None);
+        assert_eq!(state.max_block_number(), None);
+
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(3..7).collect();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
+        assert_eq!(state.min_block_number(), Some(3));
+        assert_eq!(state.max_block_number(), Some(6));
+
+        state.remove_persisted_blocks(blocks[1].recovered_block().num_hash());
+        assert_eq!(state.min_block_number(), Some(5));
+        assert_eq!(state.max_block_number(), Some(6));
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_unpersisted_blocks() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        assert!(state.unpersisted_blocks(0).is_empty());
+
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..5).collect();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
+
+        let num_hashes: Vec<_> =
+            blocks.iter().map(|block| block.recovered_block().num_hash()).collect();
+        assert_eq!(state.unpersisted_blocks(0), num_hashes);
+        assert_eq!(state.unpersisted_blocks(2), num_hashes[2..]);
+        assert!(state.unpersisted_blocks(4).is_empty());
+        assert!(state.unpersisted_blocks(u64::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_prune_forks() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        assert_eq!(state.prune_forks(), 0);
+
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..5).collect();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks[..2].to_vec() });
+
+        // a side block at height 3 that is later abandoned
+        let fork = test_block_builder
+            .get_executed_block_with_number(3, blocks[1].recovered_block().hash());
+        state.update_chain(NewCanonicalChain::Commit { new: vec![fork.clone()] });
+
+        // advance the head past the fork on the original chain
+        state.update_chain(NewCanonicalChain::Commit { new: blocks[2..].to_vec() });
+        assert_eq!(state.num_blocks(), 5);
+
+        assert_eq!(state.prune_forks(), 1);
+        assert_eq!(state.num_blocks(), 4);
+        assert!(state.state_by_hash(fork.recovered_block().hash()).is_none());
+        for block in &blocks {
+            assert!(state.state_by_hash(block.recovered_block().hash()).is_some());
+        }
+
+        // nothing left to prune
+        assert_eq!(state.prune_forks(), 0);
+    }
+
+    #[test]
+    fn test_in_memory_state_fork_metric() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let forks = || {
+            snapshotter.snapshot().into_vec().into_iter().find_map(|(key, _, _, value)| {
+                (key.key().name() == "blockchain_tree.in_mem_state.forks").then_some(value)
+            })
+        };
+
+        metrics::with_local_recorder(&recorder, || {
+            let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+            let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+            let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..4).collect();
+            state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
+            assert_eq!(forks(), Some(DebugValue::Counter(0)));
+
+            // a competing block at the head's height
+            let fork = test_block_builder
+                .get_executed_block_with_number(3, blocks[1].recovered_block().hash());
+            state.update_chain(NewCanonicalChain::Reorg {
+                new: vec![fork.clone()],
+                old: vec![blocks[2].block.clone()],
+            });
+            assert_eq!(forks(), Some(DebugValue::Counter(1)));
+
+            // extending the new head is not a fork
+            let child =
+                test_block_builder.get_executed_block_with_number(4, fork.recovered_block().hash());
+            state.update_chain(NewCanonicalChain::Commit { new: vec![child.clone()] });
+            assert_eq!(forks(), Some(DebugValue::Counter(1)));
+
+            // unwinding to an in memory ancestor is not a fork
+            state.update_chain(NewCanonicalChain::Reorg {
+                new: vec![blocks[1].clone()],
+                old: vec![fork.block, child.block],
+            });
+            assert_eq!(forks(), Some(DebugValue::Counter(1)));
+            assert_eq!(state.max_block_number(), Some(2));
+        });
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_reorg_to() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..4).collect();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
+
+        // fork off block 1 with two new blocks
+        let fork_1 = test_block_builder
+            .get_executed_block_with_number(2, blocks[0].recovered_block().hash());
+        let fork_2 =
+            test_block_builder.get_executed_block_with_number(3, fork_1.recovered_block().hash());
+        state.reorg_to(vec![fork_1.clone(), fork_2.clone()]);
+
+        let head = state.head_state().unwrap();
+        assert_eq!(head.hash(), fork_2.recovered_block().hash());
+        assert_eq!(state.hash_by_number(2), Some(fork_1.recovered_block().hash()));
+        assert_eq!(state.hash_by_number(1), Some(blocks[0].recovered_block().hash()));
+        assert!(state.state_by_hash(blocks[1].recovered_block().hash()).is_none());
+        assert!(state.state_by_hash(blocks[2].recovered_block().hash()).is_none());
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_subscribe_with_replay() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let notifications: Vec<_> = test_block_builder
+            .get_executed_blocks(1..5)
+            .map(|block| NewCanonicalChain::Commit { new: vec![block] }.to_chain_notification())
+            .collect();
+
+        for notification in &notifications[..3] {
+            state.notify_canon_state(notification.clone());
+        }
+
+        let mut replay = Box::pin(state.subscribe_with_replay(2));
+        state.notify_canon_state(notifications[3].clone());
+
+        let mut cx = Context::from_waker(Waker::noop());
+        for expected in &notifications[1..] {
+            let Poll::Ready(Some(notification)) = replay.as_mut().poll_next(&mut cx) else {
+                panic!("expected notification")
+            };
+            assert_eq!(&notification, expected);
+        }
+        assert!(replay.as_mut().poll_next(&mut cx).is_pending());
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_subscribe_canon_state_with_seq() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let notifications: Vec<_> = test_block_builder
+            .get_executed_blocks(1..5)
+            .map(|block| NewCanonicalChain::Commit { new: vec![block] }.to_chain_notification())
+            .collect();
+
+        // notifications sent before subscribing still advance the sequence
+        state.notify_canon_state(notifications[0].clone());
+
+        let mut subscription = state.subscribe_canon_state_with_seq();
+        for notification in &notifications[1..] {
+            state.notify_canon_state(notification.clone());
+        }
+
+        for (expected_seq, expected) in (2..).zip(&notifications[1..]) {
+            let (seq, notification) = subscription.try_recv().unwrap();
+            assert_eq!(seq, expected_seq);
+            assert_eq!(&notification, expected);
+        }
+        assert!(subscription.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_subscribe_head_changes() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        // the stream does not borrow the state it was created from
+        let subscriber = state.clone();
+        let mut head_changes = Box::pin(subscriber.subscribe_head_changes());
+        drop(subscriber);
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(head_changes.as_mut().poll_next(&mut cx).is_pending());
+
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..3).collect();
+        let chain = NewCanonicalChain::Commit { new: blocks.clone() };
+        state.notify_canon_state(chain.to_chain_notification());
+        state.update_chain(chain);
+
+        let Poll::Ready(Some(header)) = head_changes.as_mut().poll_next(&mut cx) else {
+            panic!("expected head change")
+        };
+        assert_eq!(header, blocks[1].recovered_block().clone_sealed_header());
+        assert!(head_changes.as_mut().poll_next(&mut cx).is_pending());
+    }
+
+    #[test]
+    fn test_in_memory_state_with_capacity() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::with_capacity(64);
+        assert!(state.inner.in_memory_state.blocks.read().capacity() >= 64);
+        assert_eq!(state.num_blocks(), 0);
+    }
+
+    #[test]
+    fn test_in_memory_state_num_blocks_and_has_pending() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        assert_eq!(state.num_blocks(), 0);
+        assert!(!state.has_pending());
+
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..4).collect();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
+        assert_eq!(state.num_blocks(), 3);
+        assert!(!state.has_pending());
+
+        let pending = test_block_builder
+            .get_executed_block_with_number(4, blocks[2].recovered_block().hash());
+        state.set_pending_block(pending);
+        assert_eq!(state.num_blocks(), 3);
+        assert!(state.has_pending());
+    }
+
+    #[test]
+    fn test_in_memory_state_canonical_tip_receipts() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        assert_eq!(state.canonical_tip_receipts(), None);
+
+        let receipts = vec![vec![Receipt { cumulative_gas_used: 21_000, ..Default::default() }]];
+        let head =
+            test_block_builder.get_executed_block_with_receipts(receipts.clone(), B256::random());
+        state.update_chain(NewCanonicalChain::Commit { new: vec![head] });
+        assert_eq!(state.canonical_tip_receipts(), Some(receipts[0].clone()));
+    }
+
+    #[test]
+    fn test_in_memory_state_pending_transactions_count() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        assert_eq!(state.pending_transactions_count(), None);
+
+        let pending = test_block_builder.get_executed_block_with_number(1, B256::random());
+        let expected = pending.recovered_block().body().transactions().count();
+        state.set_pending_block(pending);
+        assert_eq!(state.pending_transactions_count(), Some(expected));
+    }
+
+    #[test]
+    fn test_in_memory_state_pending_or_head_state() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        assert!(state.pending_or_head_state().is_none());
+
+        let head = test_block_builder.get_executed_block_with_number(1, B256::random());
+        let pending =
+            test_block_builder.get_executed_block_with_number(2, head.recovered_block().hash());
+        state.update_chain(NewCanonicalChain::Commit { new: vec![head.clone()] });
+        assert_eq!(state.pending_or_head_state().unwrap().hash(), head.recovered_block().hash());
+
+        state.set_pending_block(pending.clone());
+        assert_eq!(state.pending_or_head_state().unwrap().hash(), pending.recovered_block().hash());
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_base_fees() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        assert_eq!(state.pending_base_fee(), None);
+        assert_eq!(state.canonical_tip_base_fee(), None);
+
+        let head = SealedHeader::seal_slow(Header {
+            number: 1,
+            base_fee_per_gas: Some(7),
+            ..Default::default()
+        });
+        state.set_canonical_head(head.clone());
+        assert_eq!(state.canonical_tip_base_fee(), Some(7));
+
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        state.set_pending_block(test_block_builder.get_executed_block_with_number(2, head.hash()));
+        assert_eq!(state.pending_base_fee(), Some(INITIAL_BASE_FEE));
+        assert_eq!(state.canonical_tip_base_fee(), Some(7));
+    }
+
+    #[test]
+    fn test_canonical_in_memory_state_pending_roots() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        assert_eq!(state.pending_transactions_root(), None);
+        assert_eq!(state.pending_ommers_hash(), None);
+        assert_eq!(state.pending_withdrawals_root(), None);
+
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let pending = test_block_builder.get_executed_block_with_number(1, B256::random());
+        state.set_pending_block(pending.clone());
+
+        let header = pending.recovered_block().header();
+        assert_eq!(
+            state.pending_transactions_root(),
+            Some(calculate_transaction_root(&pending.recovered_block().body().transactions))
+        );
+        assert_eq!(state.pending_transactions_root(), Some(header.transactions_root));
+        assert_eq!(state.pending_ommers_hash(), Some(EMPTY_OMMER_ROOT_HASH));
+        assert_eq!(state.pending_withdrawals_root(), Some(calculate_withdrawals_root(&[])));
+    }
+
     #[test]
     fn test_canonical_in_memory_state_state_provider() {
         let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
@@ -1377,6 +2660,28 @@ mod tests {
         assert_eq!(empty_overlay_provider.in_memory.len(), 0);
     }
 
+    #[test]
+    fn test_canonical_in_memory_state_latest_state_provider() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let provider = state.latest_state_provider(Box::new(MockStateProvider));
+        assert!(provider.in_memory.is_empty());
+
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..4).collect();
+        state.update_chain(NewCanonicalChain::Commit { new: blocks.clone() });
+
+        let provider = state.latest_state_provider(Box::new(MockStateProvider));
+        assert_eq!(provider.in_memory.len(), 3);
+        assert_eq!(
+            provider.in_memory[0].recovered_block().hash(),
+            blocks[2].recovered_block().hash()
+        );
+        assert_eq!(
+            provider.in_memory[2].recovered_block().hash(),
+            blocks[0].recovered_block().hash()
+        );
+    }
+
     #[test]
     fn test_canonical_in_memory_state_canonical_chain_empty() {
         let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
@@ -1503,6 +2808,129 @@ mod tests {
         assert_eq!(block_state_chain[0].block().recovered_block().number, 1);
     }
 
+    #[test]
+    fn test_block_state_depth() {
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let chain = create_mock_state_chain(&mut test_block_builder, 4);
+
+        for (depth, state) in chain.iter().enumerate() {
+            assert_eq!(state.depth(), depth);
+            assert_eq!(state.depth(), state.parent_state_chain().len());
+        }
+    }
+
+    #[test]
+    fn test_executed_block_is_empty_and_gas_used() {
+        let empty: ExecutedBlock = ExecutedBlock::default();
+        assert!(empty.is_empty());
+        assert_eq!(empty.gas_used(), 0);
+
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let block = std::iter::repeat_with(|| {
+            test_block_builder.get_executed_block_with_number(1, B256::random()).block
+        })
+        .find(|block| block.recovered_block().body().transaction_count() > 0)
+        .unwrap();
+        assert!(!block.is_empty());
+        assert_eq!(block.gas_used(), block.recovered_block().gas_used);
+        assert!(block.gas_used() > 0);
+    }
+
+    #[test]
+    fn test_executed_block_withdrawals_and_blob_fields() {
+        let empty: ExecutedBlock = ExecutedBlock::default();
+        assert!(empty.withdrawals().is_none());
+        assert_eq!(empty.blob_gas_used(), None);
+        assert_eq!(empty.excess_blob_gas(), None);
+        assert_eq!(empty.blob_versioned_hashes_iter().count(), 0);
+
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let block = test_block_builder.get_executed_block_with_number(1, B256::random()).block;
+        assert_eq!(block.withdrawals(), block.recovered_block().body().withdrawals.as_ref());
+        assert!(block.withdrawals().is_some());
+        assert_eq!(block.blob_gas_used(), Some(0));
+        assert_eq!(block.excess_blob_gas(), Some(0));
+    }
+
+    #[test]
+    fn test_executed_block_header_summary() {
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let block = test_block_builder.get_executed_block_with_number(1, B256::random()).block;
+        let header = block.recovered_block().header();
+        assert_eq!(
+            block.header_summary(),
+            HeaderSummary {
+                number: header.number,
+                hash: block.recovered_block().hash(),
+                parent_hash: header.parent_hash,
+                state_root: header.state_root,
+                gas_used: header.gas_used,
+                gas_limit: header.gas_limit,
+                timestamp: header.timestamp,
+                base_fee_per_gas: header.base_fee_per_gas,
+            }
+        );
+    }
+
+    #[test]
+    fn test_executed_block_logs() {
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let log = |topic| Log::new_unchecked(Address::random(), vec![topic], Bytes::default());
+        let first = vec![log(B256::random()), log(B256::random())];
+        let second = vec![log(B256::random())];
+        let block = test_block_builder.get_executed_block_with_receipts(
+            vec![vec![
+                Receipt { logs: first.clone(), ..Default::default() },
+                Receipt { logs: second.clone(), ..Default::default() },
+            ]],
+            B256::random(),
+        );
+
+        let logs: Vec<_> = block.logs().collect();
+        assert_eq!(logs, vec![(0, &first[0]), (0, &first[1]), (1, &second[0])]);
+        assert_eq!(ExecutedBlock::<EthPrimitives>::default().logs().count(), 0);
+    }
+
+    #[test]
+    fn test_executed_block_receipts_with_bloom() {
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let address = Address::random();
+        let topic = B256::random();
+        let receipt = Receipt {
+            logs: vec![Log::new_unchecked(address, vec![topic], Bytes::default())],
+            ..Default::default()
+        };
+        let block = test_block_builder.get_executed_block_with_receipts(
+            vec![vec![receipt.clone(), Receipt::default()]],
+            B256::random(),
+        );
+
+        let mut expected = Bloom::ZERO;
+        expected.accrue(BloomInput::Raw(address.as_slice()));
+        expected.accrue(BloomInput::Raw(topic.as_slice()));
+
+        let receipts = block.receipts_with_bloom();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0].receipt, receipt);
+        assert_eq!(receipts[0].logs_bloom, expected);
+        assert_eq!(receipts[1].logs_bloom, Bloom::ZERO);
+    }
+
+    #[test]
+    fn test_executed_block_to_recovered_block() {
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let block = test_block_builder.get_executed_block_with_number(1, B256::random());
+
+        let recovered = block.to_recovered_block();
+
+        assert_eq!(recovered.senders(), block.recovered_block().senders());
+        assert_eq!(
+            recovered.body().transactions().collect::<Vec<_>>(),
+            block.recovered_block().body().transactions().collect::<Vec<_>>()
+        );
+        assert_eq!(recovered.hash(), block.recovered_block().hash());
+    }
+
     #[test]
     fn test_to_chain_notification() {
         // Generate 4 blocks
@@ -1559,4 +2987,73 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_validate_continuity() {
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..4).collect();
+        assert_eq!(NewCanonicalChain::Commit { new: blocks.clone() }.validate_continuity(), Ok(()));
+
+        // gap in block numbers
+        let gap = NewCanonicalChain::Reorg {
+            new: vec![blocks[0].clone(), blocks[2].clone()],
+            old: vec![],
+        };
+        assert_eq!(
+            gap.validate_continuity(),
+            Err(ChainContinuityError::NumberGap { number: 3, previous: 1 })
+        );
+
+        // parent hash mismatch
+        let orphan = test_block_builder.get_executed_block_with_number(2, B256::random());
+        let mismatch = NewCanonicalChain::Commit { new: vec![blocks[0].clone(), orphan.clone()] };
+        assert_eq!(
+            mismatch.validate_continuity(),
+            Err(ChainContinuityError::ParentHashMismatch {
+                number: 2,
+                parent_hash: orphan.recovered_block().parent_hash(),
+                expected: blocks[0].recovered_block().hash(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_new_canonical_chain_blocks_iter() {
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let blocks: Vec<_> = test_block_builder.get_executed_blocks(1..4).collect();
+        let fork = test_block_builder
+            .get_executed_block_with_number(3, blocks[1].recovered_block().hash());
+
+        let commit = NewCanonicalChain::Commit { new: blocks.clone() };
+        assert_eq!(commit.blocks_iter().count(), 3);
+        assert_eq!(commit.reorged_iter().count(), 0);
+        assert!(commit.blocks_iter().eq(blocks.iter().map(|block| &block.block)));
+
+        let reorg = NewCanonicalChain::Reorg {
+            new: vec![fork.clone()],
+            old: vec![blocks[2].block.clone()],
+        };
+        assert_eq!(reorg.blocks_iter().count(), 1);
+        assert_eq!(reorg.reorged_iter().count(), 1);
+        assert_eq!(reorg.blocks_iter().next(), Some(&fork.block));
+        assert_eq!(reorg.reorged_iter().next(), Some(&blocks[2].block));
+    }
+
+    #[test]
+    fn test_into_notification() {
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let block0 = test_block_builder.get_executed_block_with_number(0, B256::random());
+        let block1 =
+            test_block_builder.get_executed_block_with_number(1, block0.recovered_block.hash());
+        let block1a =
+            test_block_builder.get_executed_block_with_number(1, block0.recovered_block.hash());
+
+        let chain_commit = NewCanonicalChain::Commit { new: vec![block0, block1.clone()] };
+        let expected = chain_commit.to_chain_notification();
+        assert_eq!(chain_commit.into_notification(), expected);
+
+        let chain_reorg = NewCanonicalChain::Reorg { new: vec![block1a], old: vec![block1.block] };
+        let expected = chain_reorg.to_chain_notification();
+        assert_eq!(chain_reorg.into_notification(), expected);
+    }
 }
@@ -1,9 +1,11 @@
 use crate::{
-    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+    common::KeyValue,
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, DupWalker},
     table::{DupSort, Encode, Table},
+    tables::RawTable,
     DatabaseError,
 };
-use std::fmt::Debug;
+use std::{fmt::Debug, io::Write, ops::RangeBounds};
 
 /// Read only transaction
 pub trait DbTx: Debug + Send + Sync {
@@ -21,21 +23,111 @@ pub trait DbTx: Debug + Send + Sync {
         &self,
         key: &<T::Key as Encode>::Encoded,
     ) -> Result<Option<T::Value>, DatabaseError>;
+    /// Get values for multiple keys, in the same order as the keys.
+    fn get_many<T: Table>(&self, keys: &[T::Key]) -> Result<Vec<Option<T::Value>>, DatabaseError> {
+        keys.iter().map(|key| self.get::<T>(key.clone())).collect()
+    }
+    /// Get value by an owned key, falling back to the default value if the key is not present.
+    fn get_or_default<T: Table>(&self, key: T::Key) -> Result<T::Value, DatabaseError>
+    where
+        T::Value: Default,
+    {
+        Ok(self.get::<T>(key)?.unwrap_or_default())
+    }
     /// Commit for read only transaction will consume and free transaction and allows
     /// freeing of memory pages
     fn commit(self) -> Result<bool, DatabaseError>;
     /// Aborts transaction
     fn abort(self);
     /// Iterate over read only values in table.
+    ///
+    /// The cursor reads from the same snapshot as [`DbTx::get`], so writes committed by other
+    /// transactions after this transaction was opened are not visible during iteration.
     fn cursor_read<T: Table>(&self) -> Result<Self::Cursor<T>, DatabaseError>;
+    /// Iterate over read only values in table, starting at the given key.
+    ///
+    /// The returned cursor is already positioned at `start`, or at the next greater key if `start`
+    /// does not exist, so [`DbCursorRO::current`] yields that entry and [`DbCursorRO::next`]
+    /// continues after it without seeking again.
+    fn cursor_read_from<T: Table>(&self, start: T::Key) -> Result<Self::Cursor<T>, DatabaseError> {
+        let mut cursor = self.cursor_read::<T>()?;
+        cursor.seek(start)?;
+        Ok(cursor)
+    }
+    /// Collects all entries of the table within the given key range into an owned vector.
+    ///
+    /// Unlike the cursor walkers, the returned rows are not tied to the lifetime of a cursor.
+    fn collect_table<T: Table>(
+        &self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<Vec<KeyValue<T>>, DatabaseError> {
+        self.cursor_read::<T>()?.walk_range(range)?.collect()
+    }
+    /// Writes all entries of the table to the given writer without decoding them, returning the
+    /// number of written rows.
+    ///
+    /// Each row is written as the encoded key followed by the compressed value, both prefixed with
+    /// their length as a big-endian `u32`. Fails if a field does not fit that length prefix.
+    fn dump_table<T: Table, W: Write>(&self, writer: &mut W) -> Result<usize, DatabaseError> {
+        let mut cursor = self.cursor_read::<RawTable<T>>()?;
+        let mut rows = 0;
+        for entry in cursor.walk(None)? {
+            let (key, value) = entry?;
+            for bytes in [key.raw_key().as_slice(), value.raw_value()] {
+                let len = u32::try_from(bytes.len()).map_err(|_| {
+                    DatabaseError::Other(format!(
+                        "{} byte field does not fit the u32 length prefix",
+                        bytes.len()
+                    ))
+                })?;
+                writer
+                    .write_all(&len.to_be_bytes())
+                    .and_then(|_| writer.write_all(bytes))
+                    .map_err(|err| DatabaseError::Other(err.to_string()))?;
+            }
+            rows += 1;
+        }
+        Ok(rows)
+    }
     /// Iterate over read only values in dup sorted table.
     fn cursor_dup_read<T: DupSort>(&self) -> Result<Self::DupCursor<T>, DatabaseError>;
+    /// Returns all duplicate values stored under the given key of a dup sorted table.
+    fn dup_values<T: DupSort>(&self, key: T::Key) -> Result<Vec<T::Value>, DatabaseError> {
+        let mut cursor = self.cursor_dup_read::<T>()?;
+        let Some(walker) = walk_dup_key(&mut cursor, key)? else { return Ok(Vec::new()) };
+        walker.map(|entry| entry.map(|(_, value)| value)).collect()
+    }
+    /// Returns the number of duplicate values stored under the given key of a dup sorted table.
+    fn prefix_count<T: DupSort>(&self, key: T::Key) -> Result<usize, DatabaseError> {
+        let mut cursor = self.cursor_dup_read::<T>()?;
+        let Some(mut walker) = walk_dup_key(&mut cursor, key)? else { return Ok(0) };
+        walker.try_fold(0, |count, entry| entry.map(|_| count + 1))
+    }
     /// Returns number of entries in the table.
     fn entries<T: Table>(&self) -> Result<usize, DatabaseError>;
+    /// Returns `true` if the table has no entries.
+    ///
+    /// Only looks up the first entry instead of counting all of them like [`DbTx::entries`].
+    fn is_empty<T: Table>(&self) -> Result<bool, DatabaseError> {
+        Ok(self.cursor_read::<T>()?.first()?.is_none())
+    }
     /// Disables long-lived read transaction safety guarantees.
     fn disable_long_read_transaction_safety(&mut self);
 }
 
+/// Returns a walker over the duplicate values of the given key, or `None` if the key does not
+/// exist.
+fn walk_dup_key<T: DupSort, C: DbDupCursorRO<T> + DbCursorRO<T>>(
+    cursor: &mut C,
+    key: T::Key,
+) -> Result<Option<DupWalker<'_, T, C>>, DatabaseError> {
+    // `walk_dup` starts at the next greater key if the key does not exist
+    if cursor.seek_exact(key.clone())?.is_none() {
+        return Ok(None)
+    }
+    cursor.walk_dup(Some(key), None).map(Some)
+}
+
 /// Read write transaction that allows writing to database
 pub trait DbTxMut: Send + Sync {
     /// Read-Write Cursor type
@@ -56,9 +148,35 @@ pub trait DbTxMut: Send + Sync {
     fn append<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
         self.put::<T>(key, value)
     }
+    /// Put value to database only if the key is not present yet.
+    ///
+    /// Returns `true` if the value was written, `false` if the key already existed.
+    fn put_if_absent<T: Table>(&self, key: T::Key, value: T::Value) -> Result<bool, DatabaseError> {
+        if self.cursor_write::<T>()?.seek_exact(key.clone())?.is_some() {
+            return Ok(false)
+        }
+        self.put::<T>(key, value)?;
+        Ok(true)
+    }
     /// Delete value from database
     fn delete<T: Table>(&self, key: T::Key, value: Option<T::Value>)
         -> Result<bool, DatabaseError>;
+    /// Deletes all entries within the given key range.
+    ///
+    /// Returns the number of deleted entries.
+    fn delete_range<T: Table>(
+        &self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<usize, DatabaseError> {
+        let mut cursor = self.cursor_write::<T>()?;
+        let mut walker = cursor.walk_range(range)?;
+        let mut deleted = 0;
+        while walker.next().transpose()?.is_some() {
+            walker.delete_current()?;
+            deleted += 1;
+        }
+        Ok(deleted)
+    }
     /// Clears database.
     fn clear<T: Table>(&self) -> Result<(), DatabaseError>;
     /// Cursor mut
@@ -14,7 +14,9 @@ use reth_db_api::{
     database::Database,
     database_metrics::DatabaseMetrics,
     models::ClientVersion,
+    table::Table,
     transaction::{DbTx, DbTxMut},
+    TableViewer,
 };
 use reth_libmdbx::{
     ffi, DatabaseFlags, Environment, EnvironmentFlags, Geometry, HandleSlowReadersReturnCode,
@@ -525,6 +527,36 @@ impl DatabaseEnv {
         Ok(handles)
     }
 
+    /// Returns `true` if none of the [`Tables`] contain any entries.
+    ///
+    /// [`tables::VersionHistory`] is ignored, because it is written by
+    /// [`Self::record_client_version`] whenever the database is opened, see
+    /// [`init_db`](crate::init_db).
+    ///
+    /// Only looks up the first entry of each table, see [`DbTx::is_empty`].
+    pub fn is_empty(&self) -> Result<bool, DatabaseError> {
+        struct IsEmptyViewer<'a> {
+            tx: &'a Tx<RO>,
+        }
+
+        impl TableViewer<bool> for IsEmptyViewer<'_> {
+            type Error = DatabaseError;
+
+            fn view<T: Table>(&self) -> Result<bool, Self::Error> {
+                self.tx.is_empty::<T>()
+            }
+        }
+
+        let tx = self.tx()?;
+        let viewer = IsEmptyViewer { tx: &tx };
+        for table in Tables::ALL.iter().filter(|table| **table != Tables::VersionHistory) {
+            if !table.view(&viewer)? {
+                return Ok(false)
+            }
+        }
+        Ok(true)
+    }
+
     /// Records version that accesses the database with write privileges.
     pub fn record_client_version(&self, version: ClientVersion) -> Result<(), DatabaseError> {
         if version.is_empty() {
@@ -561,6 +593,7 @@ mod tests {
     use crate::{
         tables::{
             AccountsHistory, CanonicalHeaders, Headers, PlainAccountState, PlainStorageState,
+            RawKey, RawTable, RawValue,
         },
         test_utils::*,
         AccountChangeSets,
@@ -630,6 +663,245 @@ mod tests {
         tx.commit().expect(ERROR_COMMIT);
     }
 
+    #[test]
+    fn db_delete_range() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in 0..6u64 {
+            tx.put::<CanonicalHeaders>(key, B256::with_last_byte(key as u8)).expect(ERROR_PUT);
+        }
+        assert_eq!(tx.delete_range::<CanonicalHeaders>(2..5).expect(ERROR_DEL), 3);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let remaining = tx.collect_table::<CanonicalHeaders>(..).expect(ERROR_GET);
+        assert_eq!(remaining.into_iter().map(|(key, _)| key).collect::<Vec<_>>(), vec![0, 1, 5]);
+        tx.commit().expect(ERROR_COMMIT);
+
+        // deleting an empty range is a no-op
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        assert_eq!(tx.delete_range::<CanonicalHeaders>(2..5).expect(ERROR_DEL), 0);
+        assert_eq!(tx.delete_range::<CanonicalHeaders>(5..).expect(ERROR_DEL), 1);
+        assert_eq!(tx.entries::<CanonicalHeaders>().expect(ERROR_GET), 2);
+    }
+
+    #[test]
+    fn db_dup_values() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+        let address = Address::with_last_byte(1);
+        let entries = (0..3u8)
+            .map(|i| StorageEntry { key: B256::with_last_byte(i), value: U256::from(i) })
+            .collect::<Vec<_>>();
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for entry in &entries {
+            tx.put::<PlainStorageState>(address, *entry).expect(ERROR_PUT);
+        }
+        let other = StorageEntry { key: B256::with_last_byte(9), value: U256::from(9) };
+        tx.put::<PlainStorageState>(Address::with_last_byte(2), other).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.dup_values::<PlainStorageState>(address).expect(ERROR_GET), entries);
+        assert_eq!(
+            tx.dup_values::<PlainStorageState>(Address::with_last_byte(2)).expect(ERROR_GET),
+            vec![other]
+        );
+        // an absent key does not pick up the values of the next key
+        assert!(tx
+            .dup_values::<PlainStorageState>(Address::with_last_byte(0))
+            .expect(ERROR_GET)
+            .is_empty());
+    }
+
+    #[test]
+    fn db_prefix_count() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+        let address = Address::with_last_byte(1);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for i in 0..3u8 {
+            let entry = StorageEntry { key: B256::with_last_byte(i), value: U256::from(i) };
+            tx.put::<PlainStorageState>(address, entry).expect(ERROR_PUT);
+        }
+        let other = StorageEntry { key: B256::with_last_byte(9), value: U256::from(9) };
+        tx.put::<PlainStorageState>(Address::with_last_byte(2), other).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.prefix_count::<PlainStorageState>(address), Ok(3));
+        assert_eq!(tx.prefix_count::<PlainStorageState>(Address::with_last_byte(2)), Ok(1));
+        assert_eq!(tx.prefix_count::<PlainStorageState>(Address::with_last_byte(0)), Ok(0));
+    }
+
+    #[test]
+    fn db_collect_table() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in 0..5u64 {
+            tx.put::<CanonicalHeaders>(key, B256::with_last_byte(key as u8)).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let all = tx.collect_table::<CanonicalHeaders>(..).expect(ERROR_GET);
+        assert_eq!(
+            all,
+            (0..5u64).map(|key| (key, B256::with_last_byte(key as u8))).collect::<Vec<_>>()
+        );
+
+        let range = tx.collect_table::<CanonicalHeaders>(1..3).expect(ERROR_GET);
+        assert_eq!(range, vec![(1, B256::with_last_byte(1)), (2, B256::with_last_byte(2))]);
+        tx.commit().expect(ERROR_COMMIT);
+    }
+
+    #[test]
+    fn db_cursor_read_from() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for key in [0u64, 1, 2, 4] {
+            tx.put::<CanonicalHeaders>(key, B256::with_last_byte(key as u8)).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_read_from::<CanonicalHeaders>(1).unwrap();
+        assert_eq!(cursor.current(), Ok(Some((1, B256::with_last_byte(1)))));
+        assert_eq!(cursor.next(), Ok(Some((2, B256::with_last_byte(2)))));
+
+        // a missing start key positions the cursor at the next greater key
+        let mut cursor = tx.cursor_read_from::<CanonicalHeaders>(3).unwrap();
+        assert_eq!(cursor.current(), Ok(Some((4, B256::with_last_byte(4)))));
+        assert_eq!(cursor.next(), Ok(None));
+        tx.commit().expect(ERROR_COMMIT);
+    }
+
+    #[test]
+    fn db_dump_table() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        for number in 0..3u64 {
+            tx.put::<Headers>(number, Header { number, ..Default::default() }).expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+
+        let mut dump = Vec::new();
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.dump_table::<Headers, _>(&mut dump).expect(ERROR_GET), 3);
+        let expected = tx.collect_table::<Headers>(..).expect(ERROR_GET);
+        tx.commit().expect(ERROR_COMMIT);
+
+        // re-import the dump into a fresh database
+        let restored = create_test_db(DatabaseEnvKind::RW);
+        let tx = restored.tx_mut().expect(ERROR_INIT_TX);
+        let mut bytes = dump.as_slice();
+        let mut next_field = || {
+            let (len, rest) = bytes.split_first_chunk::<4>().unwrap();
+            let (field, rest) = rest.split_at(u32::from_be_bytes(*len) as usize);
+            bytes = rest;
+            field.to_vec()
+        };
+        for _ in 0..3 {
+            let (key, value) = (next_field(), next_field());
+            tx.put::<RawTable<Headers>>(RawKey::from_vec(key), RawValue::from_vec(value))
+                .expect(ERROR_PUT);
+        }
+        tx.commit().expect(ERROR_COMMIT);
+        assert!(bytes.is_empty());
+
+        let tx = restored.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.collect_table::<Headers>(..).expect(ERROR_GET), expected);
+        tx.commit().expect(ERROR_COMMIT);
+    }
+
+    #[test]
+    fn db_env_is_empty() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+        assert!(env.is_empty().expect(ERROR_GET));
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<PlainAccountState>(Address::ZERO, Account::default()).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+        assert!(!env.is_empty().expect(ERROR_GET));
+    }
+
+    #[test]
+    fn db_is_empty() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert!(tx.is_empty::<Headers>().expect(ERROR_GET));
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<Headers>(1, Header::default()).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert!(!tx.is_empty::<Headers>().expect(ERROR_GET));
+        assert!(tx.is_empty::<CanonicalHeaders>().expect(ERROR_GET));
+    }
+
+    #[test]
+    fn db_get_many() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<CanonicalHeaders>(1, B256::with_last_byte(1)).expect(ERROR_PUT);
+        tx.put::<CanonicalHeaders>(3, B256::with_last_byte(3)).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(
+            tx.get_many::<CanonicalHeaders>(&[3, 2, 1, 4]).expect(ERROR_GET),
+            vec![Some(B256::with_last_byte(3)), None, Some(B256::with_last_byte(1)), None]
+        );
+        assert!(tx.get_many::<CanonicalHeaders>(&[]).expect(ERROR_GET).is_empty());
+    }
+
+    #[test]
+    fn db_get_or_default() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+
+        let value = Header { number: 1, ..Default::default() };
+        let key = 1u64;
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<Headers>(key, value.clone()).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.get_or_default::<Headers>(key).expect(ERROR_GET), value);
+        assert_eq!(tx.get_or_default::<Headers>(key + 1).expect(ERROR_GET), Header::default());
+    }
+
+    #[test]
+    fn db_put_if_absent() {
+        let env = create_test_db(DatabaseEnvKind::RW);
+
+        let value = Header::default();
+        let other = Header { number: 1, ..Default::default() };
+        let key = 1u64;
+
+        // Absent key is written
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        assert!(tx.put_if_absent::<Headers>(key, value.clone()).expect(ERROR_PUT));
+        tx.commit().expect(ERROR_COMMIT);
+
+        // Present key is skipped and keeps the original value
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        assert!(!tx.put_if_absent::<Headers>(key, other).expect(ERROR_PUT));
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        let result = tx.get::<Headers>(key).expect(ERROR_GET);
+        assert_eq!(result.expect(ERROR_RETURN_VALUE), value);
+    }
+
     #[test]
     fn db_dup_cursor_delete_first() {
         let db: Arc<DatabaseEnv> = create_test_db(DatabaseEnvKind::RW);
@@ -758,6 +1030,42 @@ mod tests {
         assert_eq!(walker.next(), None);
     }
 
+    #[test]
+    fn db_cursor_read_is_snapshot_isolated() {
+        let db: Arc<DatabaseEnv> = create_test_db(DatabaseEnvKind::RW);
+
+        // PUT (0, 0), (1, 0)
+        let tx = db.tx_mut().expect(ERROR_INIT_TX);
+        vec![0, 1]
+            .into_iter()
+            .try_for_each(|key| tx.put::<CanonicalHeaders>(key, B256::ZERO))
+            .expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+        let mut walker = cursor.walk(None).unwrap();
+        assert_eq!(walker.next(), Some(Ok((0, B256::ZERO))));
+
+        // Commit a new row from another thread while the walk is in progress
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                let tx = db.tx_mut().expect(ERROR_INIT_TX);
+                tx.put::<CanonicalHeaders>(2, B256::ZERO).expect(ERROR_PUT);
+                tx.commit().expect(ERROR_COMMIT);
+            });
+        });
+
+        // The walk and point lookups stay on the snapshot the transaction was opened at
+        assert_eq!(walker.next(), Some(Ok((1, B256::ZERO))));
+        assert_eq!(walker.next(), None);
+        assert_eq!(tx.get::<CanonicalHeaders>(2).expect(ERROR_GET), None);
+
+        // A new transaction observes the write
+        let tx = db.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.get::<CanonicalHeaders>(2).expect(ERROR_GET), Some(B256::ZERO));
+    }
+
     #[test]
     fn db_cursor_walk_range_on_dup_table() {
         let db: Arc<DatabaseEnv> = create_test_db(DatabaseEnvKind::RW);
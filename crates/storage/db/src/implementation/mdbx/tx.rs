@@ -347,6 +347,8 @@ enum PutKind {
     /// Append the key-value to the end of the table -- fast path when the new
     /// key is the highest so far, like the latest block number.
     Append,
+    /// Insert the key-value only if the key does not exist yet.
+    Insert,
 }
 
 impl PutKind {
@@ -358,6 +360,9 @@ impl PutKind {
             Self::Append => {
                 (Operation::PutAppend, DatabaseWriteOperation::PutAppend, WriteFlags::APPEND)
             }
+            Self::Insert => {
+                (Operation::PutInsert, DatabaseWriteOperation::PutInsert, WriteFlags::NO_OVERWRITE)
+            }
         }
     }
 }
@@ -365,25 +370,30 @@ impl PutKind {
 impl Tx<RW> {
     /// The inner implementation mapping to `mdbx_put` that supports different
     /// put kinds like upserting and appending.
+    ///
+    /// Returns `false` if the value was not written because the key already exists, which can
+    /// only happen for [`PutKind::Insert`].
     fn put<T: Table>(
         &self,
         kind: PutKind,
         key: T::Key,
         value: T::Value,
-    ) -> Result<(), DatabaseError> {
+    ) -> Result<bool, DatabaseError> {
         let key = key.encode();
         let value = value.compress();
         let (operation, write_operation, flags) = kind.into_operation_and_flags();
         self.execute_with_operation_metric::<T, _>(operation, Some(value.as_ref().len()), |tx| {
-            tx.put(self.get_dbi::<T>()?, key.as_ref(), value, flags).map_err(|e| {
-                DatabaseWriteError {
+            match tx.put(self.get_dbi::<T>()?, key.as_ref(), value, flags) {
+                Ok(()) => Ok(true),
+                Err(reth_libmdbx::Error::KeyExist) if matches!(kind, PutKind::Insert) => Ok(false),
+                Err(e) => Err(DatabaseWriteError {
                     info: e.into(),
                     operation: write_operation,
                     table_name: T::NAME,
                     key: key.into(),
                 }
-                .into()
-            })
+                .into()),
+            }
         })
     }
 }
@@ -393,11 +403,15 @@ impl DbTxMut for Tx<RW> {
     type DupCursorMut<T: DupSort> = Cursor<RW, T>;
 
     fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
-        self.put::<T>(PutKind::Upsert, key, value)
+        self.put::<T>(PutKind::Upsert, key, value).map(|_| ())
     }
 
     fn append<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
-        self.put::<T>(PutKind::Append, key, value)
+        self.put::<T>(PutKind::Append, key, value).map(|_| ())
+    }
+
+    fn put_if_absent<T: Table>(&self, key: T::Key, value: T::Value) -> Result<bool, DatabaseError> {
+        self.put::<T>(PutKind::Insert, key, value)
     }
 
     fn delete<T: Table>(
@@ -268,6 +268,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn db_is_empty_after_init() {
+        let path = tempdir().unwrap();
+        let version = ClientVersion { version: String::from("v1"), ..Default::default() };
+
+        let db = init_db(&path, DatabaseArguments::new(version)).unwrap();
+        let tx = db.tx().unwrap();
+        assert!(!tx.is_empty::<tables::VersionHistory>().unwrap());
+        tx.commit().unwrap();
+        assert!(db.is_empty().unwrap());
+    }
+
     #[test]
     fn db_client_version() {
         let path = tempdir().unwrap();
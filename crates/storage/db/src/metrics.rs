@@ -201,6 +201,8 @@ pub(crate) enum Operation {
     PutUpsert,
     /// Database put append operation.
     PutAppend,
+    /// Database put insert operation.
+    PutInsert,
     /// Database delete operation.
     Delete,
     /// Database cursor upsert operation.
@@ -224,6 +226,7 @@ impl Operation {
             Self::Get => "get",
             Self::PutUpsert => "put-upsert",
             Self::PutAppend => "put-append",
+            Self::PutInsert => "put-insert",
             Self::Delete => "delete",
             Self::CursorUpsert => "cursor-upsert",
             Self::CursorInsert => "cursor-insert",
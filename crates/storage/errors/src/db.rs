@@ -110,6 +110,8 @@ pub enum DatabaseWriteOperation {
     PutUpsert,
     /// Put append.
     PutAppend,
+    /// Put insert.
+    PutInsert,
 }
 
 /// Database log level.